@@ -0,0 +1,71 @@
+//! broot's user configuration, typically loaded from a `conf.toml` file
+//! in broot's config directory.
+
+use serde::Deserialize;
+
+/// a named file-type definition, as declared in the `[[file_types]]`
+/// array of tables of the configuration (see `crate::file_types::FileTypes`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileTypeConf {
+    pub name: String,
+    pub globs: Vec<String>,
+}
+
+/// the whole of broot's user configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Conf {
+    /// user-defined override globs (see `crate::git::ignore::Override`):
+    /// a plain pattern force-hides a path, a `!`-prefixed one force-shows
+    /// it, and they take priority over every `.gitignore` file
+    #[serde(default)]
+    pub file_overrides: Vec<String>,
+
+    /// user-defined file-type definitions (see `crate::file_types::FileTypes`),
+    /// merged with, and overriding name for name, the built-in ones
+    #[serde(default)]
+    pub file_types: Vec<FileTypeConf>,
+}
+
+impl Conf {
+    /// parse a configuration from its TOML content
+    pub fn from_str(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_overrides_and_file_types_from_toml() {
+        let conf = Conf::from_str(
+            r#"
+            file_overrides = ["node_modules", "!.env"]
+
+            [[file_types]]
+            name = "rust"
+            globs = ["*.rs", "*.rlib"]
+
+            [[file_types]]
+            name = "web"
+            globs = ["*.html", "*.css", "*.js"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            conf.file_overrides,
+            vec!["node_modules".to_string(), "!.env".to_string()],
+        );
+        assert_eq!(conf.file_types.len(), 2);
+        assert_eq!(conf.file_types[0].name, "rust");
+        assert_eq!(conf.file_types[0].globs, vec!["*.rs".to_string(), "*.rlib".to_string()]);
+    }
+
+    #[test]
+    fn defaults_to_empty_when_absent() {
+        let conf = Conf::from_str("").unwrap();
+        assert!(conf.file_overrides.is_empty());
+        assert!(conf.file_types.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! Named file-type definitions, similar to ripgrep's `types`: a name
+//! (e.g. "rust", "web") mapped to a list of glob patterns.
+//!
+//! `Verb::check_args` consults `is_of_type` to reject a verb whose
+//! `file_type` doesn't match the selection.
+//!
+//! The tree filter doesn't use these types: a pattern or search can't be
+//! restricted to a type.
+
+use {
+    globset::{Candidate, GlobBuilder, GlobSet, GlobSetBuilder},
+    std::path::Path,
+};
+
+/// the built-in file types, used when the configuration doesn't
+/// define (or override) its own
+fn default_definitions() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![
+        ("rust", &["*.rs"]),
+        ("web", &["*.html", "*.css", "*.js", "*.ts", "*.jsx", "*.tsx"]),
+        ("markdown", &["*.md", "*.markdown"]),
+        ("toml", &["*.toml"]),
+        ("yaml", &["*.yml", "*.yaml"]),
+        ("image", &["*.png", "*.jpg", "*.jpeg", "*.gif", "*.svg", "*.bmp"]),
+    ]
+}
+
+/// a compiled set of named file types: classifying a path is a single
+/// `GlobSet` scan, the way the gitignore rules are matched
+pub struct FileTypes {
+    /// name of the type owning the glob at the same index in `set`
+    names: Vec<String>,
+    set: GlobSet,
+}
+
+impl FileTypes {
+    /// build a `FileTypes` from an ordered list of `(name, globs)` definitions.
+    /// When a path matches globs of several definitions, the last one wins.
+    /// Definitions sharing a name add up their globs.
+    pub fn new<I, S>(definitions: I) -> Self
+    where
+        I: IntoIterator<Item = (S, Vec<String>)>,
+        S: Into<String>,
+    {
+        let mut names = Vec::new();
+        let mut builder = GlobSetBuilder::new();
+        for (name, globs) in definitions {
+            let name = name.into();
+            for glob in globs {
+                if let Ok(glob) = GlobBuilder::new(&glob).literal_separator(false).build() {
+                    builder.add(glob);
+                    names.push(name.clone());
+                }
+            }
+        }
+        let set = builder.build().unwrap_or_else(|_| GlobSet::empty());
+        Self { names, set }
+    }
+
+    /// the built-in file types (rust, web, markdown, ...)
+    pub fn default_types() -> Self {
+        Self::new(
+            default_definitions()
+                .into_iter()
+                .map(|(name, globs)| (name, globs.iter().map(|g| g.to_string()).collect())),
+        )
+    }
+
+    /// build the registry used by the app: the built-in types, extended
+    /// (or overridden, name for name) by the `file_types` entries of
+    /// broot's configuration
+    pub fn from_conf(conf: &crate::conf::Conf) -> Self {
+        let builtins = default_definitions()
+            .into_iter()
+            .filter(|(name, _)| !conf.file_types.iter().any(|def| def.name == *name))
+            .map(|(name, globs)| (name.to_string(), globs.iter().map(|g| g.to_string()).collect()));
+        let configured = conf
+            .file_types
+            .iter()
+            .map(|def| (def.name.clone(), def.globs.clone()));
+        Self::new(builtins.chain(configured))
+    }
+
+    /// classify a path by its filename, returning the name of the last
+    /// matching type: definitions added later (e.g. configured types,
+    /// chained after the builtins in `from_conf`) take precedence, the
+    /// same "last one wins" precedence as the gitignore rules.
+    pub fn type_of(&self, path: &Path) -> Option<&str> {
+        let filename = path.file_name()?.to_str()?;
+        let candidate = Candidate::new(filename);
+        self.set
+            .matches_candidate(&candidate)
+            .into_iter()
+            .max()
+            .and_then(|idx| self.names.get(idx))
+            .map(String::as_str)
+    }
+
+    /// whether the path belongs to the named type
+    pub fn is_of_type(&self, path: &Path, type_name: &str) -> bool {
+        self.type_of(path) == Some(type_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_types_classify_by_extension() {
+        let file_types = FileTypes::default_types();
+        assert_eq!(file_types.type_of(Path::new("src/main.rs")), Some("rust"));
+        assert_eq!(file_types.type_of(Path::new("index.html")), Some("web"));
+        assert_eq!(file_types.type_of(Path::new("README.md")), Some("markdown"));
+        assert_eq!(file_types.type_of(Path::new("unknown.xyz")), None);
+    }
+
+    #[test]
+    fn is_of_type_matches_the_named_type_only() {
+        let file_types = FileTypes::default_types();
+        assert!(file_types.is_of_type(Path::new("lib.rs"), "rust"));
+        assert!(!file_types.is_of_type(Path::new("lib.rs"), "web"));
+    }
+
+    /// a type definition added after the builtins (as `from_conf` does with
+    /// configured types) must win over a builtin claiming the same glob
+    #[test]
+    fn later_definition_overrides_earlier_one_on_the_same_glob() {
+        let file_types = FileTypes::new(vec![
+            ("image".to_string(), vec!["*.png".to_string()]),
+            ("icons".to_string(), vec!["*.png".to_string()]),
+        ]);
+        assert_eq!(file_types.type_of(Path::new("logo.png")), Some("icons"));
+    }
+
+    #[test]
+    fn from_conf_merges_configured_types_with_builtins_and_overrides_by_name() {
+        let conf = crate::conf::Conf::from_str(
+            r#"
+            [[file_types]]
+            name = "image"
+            globs = ["*.png", "*.heic"]
+
+            [[file_types]]
+            name = "data"
+            globs = ["*.csv", "*.json"]
+            "#,
+        )
+        .unwrap();
+        let file_types = FileTypes::from_conf(&conf);
+
+        // the builtin "rust" type is still there
+        assert_eq!(file_types.type_of(Path::new("lib.rs")), Some("rust"));
+        // the configured "image" type replaces the builtin one, with its own globs
+        assert_eq!(file_types.type_of(Path::new("photo.heic")), Some("image"));
+        // a type only declared in the configuration is picked up
+        assert_eq!(file_types.type_of(Path::new("table.csv")), Some("data"));
+    }
+}
@@ -2,7 +2,7 @@
 
 use {
     git2,
-    glob,
+    globset::{Candidate, GlobBuilder, GlobSet, GlobSetBuilder},
     id_arena::{Arena, Id},
     lazy_static::lazy_static,
     lazy_regex::regex,
@@ -17,20 +17,73 @@ pub fn is_repo(root: &Path) -> bool {
     root.join(".git").exists()
 }
 
-/// a simple rule of a gitignore file
+/// resolve the `$GIT_DIR` of a repository whose root is `repo_root`.
+/// `.git` is usually a directory but, for worktrees and submodules, it's
+/// a file containing a `gitdir: <path>` line pointing to the real one.
+fn git_dir(repo_root: &Path) -> Option<PathBuf> {
+    let dot_git = repo_root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let gitdir = content.trim().strip_prefix("gitdir:")?.trim();
+    let gitdir = PathBuf::from(gitdir);
+    Some(if gitdir.is_absolute() {
+        gitdir
+    } else {
+        repo_root.join(gitdir)
+    })
+}
+
+/// resolve the *common* `$GIT_DIR` of a repository whose root is `repo_root`:
+/// the directory holding the files shared by all worktrees (`info/exclude`
+/// among them). For a normal repository or a submodule, this is `$GIT_DIR`
+/// itself. For a linked worktree, `$GIT_DIR` is `<main>/.git/worktrees/<name>`,
+/// which has no `info/` of its own: the actual common dir is found by
+/// reading the `commondir` file it contains, a path relative to itself.
+fn common_git_dir(repo_root: &Path) -> Option<PathBuf> {
+    let gitdir = git_dir(repo_root)?;
+    match std::fs::read_to_string(gitdir.join("commondir")) {
+        Ok(content) => {
+            let commondir = PathBuf::from(content.trim());
+            Some(if commondir.is_absolute() {
+                commondir
+            } else {
+                gitdir.join(commondir)
+            })
+        }
+        Err(_) => Some(gitdir),
+    }
+}
+
+/// the metadata of one rule of a gitignore file, in the order
+/// matching the globs compiled into the file's `GlobSet`
 #[derive(Clone)]
 struct GitIgnoreRule {
     ok: bool,        // does this rule when matched means the file is good? (usually false)
     directory: bool, // whether this rule only applies to directories
-    filename: bool,  // does this rule apply to just the filename
-    pattern: glob::Pattern,
-    pattern_options: glob::MatchOptions,
+    filename: bool,  // whether this rule is anchored on the filename alone (no '/' in it)
+}
+
+/// compile a rule's pattern into a glob matched against a *whole path*
+/// candidate. A pattern anchored on the filename alone (no '/' in it) is
+/// given a `**/` prefix so it still matches at any depth: without it, a
+/// wildcard-less pattern like `target` would require an exact, full-string
+/// match against the whole path and would never match a real, nested file.
+fn compile_rule_glob(rule: &GitIgnoreRule, pattern: &str) -> std::result::Result<globset::Glob, globset::Error> {
+    let pattern = if rule.filename {
+        format!("**/{}", pattern)
+    } else {
+        pattern.to_string()
+    };
+    GlobBuilder::new(&pattern).literal_separator(true).build()
 }
 
 impl GitIgnoreRule {
-    /// parse a line of a .gitignore file.
+    /// parse a line of a .gitignore file, returning the rule's metadata
+    /// along with the glob pattern to compile into the set.
     /// The ref_dir is used if the line starts with '/'
-    fn from(line: &str, ref_dir: &Path) -> Option<GitIgnoreRule> {
+    fn from(line: &str, ref_dir: &Path) -> Option<(GitIgnoreRule, String)> {
         if line.starts_with('#') {
             return None; // comment line
         }
@@ -50,30 +103,27 @@ impl GitIgnoreRule {
                 if has_separator && p.starts_with('/') {
                     p = ref_dir.to_string_lossy().to_string() + &p;
                 }
-                if let Ok(pattern) = glob::Pattern::new(&p) {
-                    let pattern_options = glob::MatchOptions {
-                        case_sensitive: true,
-                        require_literal_leading_dot: false,
-                        require_literal_separator: has_separator,
-                    };
-                    return Some(GitIgnoreRule {
+                return Some((
+                    GitIgnoreRule {
                         ok: c.get(1).is_some(), // if negation
-                        pattern,
                         directory: c.get(3).is_some(),
                         filename: !has_separator,
-                        pattern_options,
-                    });
-                }
+                    },
+                    p,
+                ));
             }
         }
         None
     }
 }
 
-/// The rules of a gitignore file
+/// The rules of a gitignore file, compiled into a single `GlobSet` so that
+/// matching a path is one regex-set scan instead of a loop of individual
+/// glob comparisons.
 #[derive(Clone)]
 pub struct GitIgnoreFile {
     rules: Vec<GitIgnoreRule>,
+    set: GlobSet,
 }
 impl GitIgnoreFile {
     /// build a new gitignore file, from either a global ignore file or
@@ -84,15 +134,34 @@ impl GitIgnoreFile {
     pub fn new(file_path: &Path, ref_dir: &Path) -> Result<GitIgnoreFile> {
         let f = File::open(file_path)?;
         let mut rules: Vec<GitIgnoreRule> = Vec::new();
+        let mut builder = GlobSetBuilder::new();
         for line in BufReader::new(f).lines() {
-            if let Some(rule) = GitIgnoreRule::from(&line?, ref_dir) {
-                rules.push(rule);
+            if let Some((rule, pattern)) = GitIgnoreRule::from(&line?, ref_dir) {
+                if let Ok(glob) = compile_rule_glob(&rule, &pattern) {
+                    builder.add(glob);
+                    rules.push(rule);
+                }
             }
         }
-        // the last rule applicable to a path is the right one. So
-        // we reverse the list to easily iterate from the last one to the first one
-        rules.reverse();
-        Ok(GitIgnoreFile { rules })
+        let set = builder.build().unwrap_or_else(|_| GlobSet::empty());
+        Ok(GitIgnoreFile { rules, set })
+    }
+    /// return, among the globs matching the given path, the rule with the
+    /// highest original line index (the last applicable rule wins), skipping
+    /// directory-only globs when the entry isn't a directory.
+    /// `None` means no rule of this file applies.
+    fn matching_rule(&self, path: &Path, directory: bool) -> Option<&GitIgnoreRule> {
+        let candidate = Candidate::new(path);
+        self.set
+            .matches_candidate(&candidate)
+            .into_iter()
+            .filter(|&idx| {
+                self.rules
+                    .get(idx)
+                    .map_or(false, |rule| !rule.directory || directory)
+            })
+            .max()
+            .and_then(|idx| self.rules.get(idx))
     }
     /// return the global gitignore file interpreted for
     /// the given repo dir
@@ -106,6 +175,12 @@ impl GitIgnoreFile {
             None
         }
     }
+    /// return the repository-local `$GIT_DIR/info/exclude` file, which git
+    /// itself always applies in addition to tracked `.gitignore` files
+    pub fn info_exclude(repo_root: &Path) -> Option<GitIgnoreFile> {
+        let exclude_path = common_git_dir(repo_root)?.join("info/exclude");
+        GitIgnoreFile::new(&exclude_path, repo_root).ok()
+    }
 }
 
 pub fn find_global_ignore() -> Option<PathBuf> {
@@ -121,23 +196,126 @@ pub fn find_global_ignore() -> Option<PathBuf> {
         })
 }
 
+/// a set of user-defined override globs, inspired by ripgrep's
+/// `--glob`/override matcher: an ordered list of `(glob, whitelist)` rules,
+/// compiled with the same grammar as `GitIgnoreRule`, that takes priority
+/// over every `.gitignore` and dedicated ignore file. A plain pattern force-
+/// hides a path, a leading `!` force-shows it, and (as usual) the last
+/// applicable rule wins.
+#[derive(Clone)]
+pub struct Override {
+    rules: Vec<GitIgnoreRule>,
+    set: GlobSet,
+}
+impl Default for Override {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            set: GlobSet::empty(),
+        }
+    }
+}
+impl Override {
+    /// build an override from an ordered list of patterns, as found in
+    /// broot's configuration
+    pub fn new(patterns: &[String]) -> Self {
+        let mut rules: Vec<GitIgnoreRule> = Vec::new();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            if let Some((rule, pattern)) = GitIgnoreRule::from(pattern, Path::new("")) {
+                if let Ok(glob) = compile_rule_glob(&rule, &pattern) {
+                    builder.add(glob);
+                    rules.push(rule);
+                }
+            }
+        }
+        let set = builder.build().unwrap_or_else(|_| GlobSet::empty());
+        Self { rules, set }
+    }
+    /// whether the override has an opinion on this path: `Some(true)` to
+    /// force it visible, `Some(false)` to force it hidden, `None` when no
+    /// override rule applies and the normal ignore chain should decide
+    fn matching_rule(&self, path: &Path, directory: bool) -> Option<bool> {
+        let candidate = Candidate::new(path);
+        self.set
+            .matches_candidate(&candidate)
+            .into_iter()
+            .filter(|&idx| {
+                self.rules
+                    .get(idx)
+                    .map_or(false, |rule| !rule.directory || directory)
+            })
+            .max()
+            .and_then(|idx| self.rules.get(idx))
+            .map(|rule| rule.ok)
+    }
+}
+
+/// names of the dedicated, non-git, broot-specific ignore files,
+/// checked in this order at every directory level
+const DEDICATED_IGNORE_FILENAMES: &[&str] = &[".ignore", ".brootignore"];
+
 #[derive(Debug, Clone, Default)]
 pub struct GitIgnoreChain {
     in_repo: bool,
     file_ids: Vec<Id<GitIgnoreFile>>,
+    /// files making the dedicated `.ignore`/`.brootignore` chain, applied
+    /// regardless of whether we're in a git repository
+    dedicated_file_ids: Vec<Id<GitIgnoreFile>>,
 }
 impl GitIgnoreChain {
     pub fn push(&mut self, id: Id<GitIgnoreFile>) {
         self.file_ids.push(id);
     }
+    pub fn push_dedicated(&mut self, id: Id<GitIgnoreFile>) {
+        self.dedicated_file_ids.push(id);
+    }
 }
 
-#[derive(Default)]
 pub struct GitIgnorer {
     files: Arena<GitIgnoreFile>,
+
+    /// whether `.gitignore` files (and the global git excludes) are honored
+    pub gitignore_enabled: bool,
+
+    /// whether the dedicated `.ignore`/`.brootignore` files are honored
+    pub dedicated_ignore_enabled: bool,
+
+    /// user-defined override globs, consulted before any `.gitignore` or
+    /// dedicated ignore file
+    pub overrides: Override,
+}
+
+impl Default for GitIgnorer {
+    fn default() -> Self {
+        Self {
+            files: Arena::new(),
+            gitignore_enabled: true,
+            dedicated_ignore_enabled: true,
+            overrides: Override::default(),
+        }
+    }
 }
 
 impl GitIgnorer {
+    /// build a new ignorer, loading the user-defined override globs
+    /// declared in broot's configuration (the `file_overrides` entries)
+    pub fn new(conf: &crate::conf::Conf) -> Self {
+        Self {
+            overrides: Override::new(&conf.file_overrides),
+            ..Self::default()
+        }
+    }
+    /// load the dedicated ignore files (`.ignore`, `.brootignore`) of `dir`,
+    /// if any, pushing them into `chain`
+    fn push_dedicated_files(&mut self, chain: &mut GitIgnoreChain, dir: &Path) {
+        for filename in DEDICATED_IGNORE_FILENAMES {
+            let ignore_file = dir.join(filename);
+            if let Ok(gif) = GitIgnoreFile::new(&ignore_file, dir) {
+                chain.push_dedicated(self.files.alloc(gif));
+            }
+        }
+    }
     pub fn root_chain(&mut self, mut dir: &Path) -> GitIgnoreChain {
         let mut chain = GitIgnoreChain::default();
         loop {
@@ -147,10 +325,14 @@ impl GitIgnorer {
                 if let Some(gif) = GitIgnoreFile::global(dir) {
                     chain.push(self.files.alloc(gif));
                 }
+                if let Some(gif) = GitIgnoreFile::info_exclude(dir) {
+                    chain.push(self.files.alloc(gif));
+                }
             }
             if let Ok(gif) = GitIgnoreFile::new(&ignore_file, dir) {
                 chain.push(self.files.alloc(gif));
             }
+            self.push_dedicated_files(&mut chain, dir);
             if is_repo {
                 chain.in_repo = true;
                 break;
@@ -168,11 +350,18 @@ impl GitIgnorer {
         // we reset the chain to the root one:
         // we don't want the .gitignore files of super repositories
         // (see https://github.com/Canop/broot/issues/160)
+        // Note that this reset is specific to gitignore files: the dedicated
+        // `.ignore`/`.brootignore` chain is independent of repo boundaries,
+        // so it's always carried over from the parent chain.
         let mut chain = if is_repo(dir) {
             let mut chain = GitIgnoreChain::default();
+            chain.dedicated_file_ids = parent_chain.dedicated_file_ids.clone();
             if let Some(gif) = GitIgnoreFile::global(dir) {
                 chain.push(self.files.alloc(gif));
             }
+            if let Some(gif) = GitIgnoreFile::info_exclude(dir) {
+                chain.push(self.files.alloc(gif));
+            }
             chain.in_repo = true;
             chain
         } else {
@@ -184,6 +373,7 @@ impl GitIgnorer {
                 chain.push(self.files.alloc(gif));
             }
         }
+        self.push_dedicated_files(&mut chain, dir);
         chain
     }
     /// return true if the given path should not be ignored
@@ -191,31 +381,145 @@ impl GitIgnorer {
         &self,
         chain: &GitIgnoreChain,
         path: &Path,
-        filename: &str,
+        _filename: &str,
         directory: bool,
     ) -> bool {
-        if !chain.in_repo {
-            // if we're not in a git repository, then .gitignore files, including
-            // the global ones, are irrelevant
-            return true;
-        }
-        // we start with deeper files: deeper rules have a bigger priority
-        for id in chain.file_ids.iter().rev() {
-            let file = &self.files[*id];
-            for rule in &file.rules {
-                if rule.directory && !directory {
-                    continue;
+        if let Some(ok) = self.overrides.matching_rule(path, directory) {
+            return ok;
+        }
+        if self.gitignore_enabled && chain.in_repo {
+            // we start with deeper files: deeper rules have a bigger priority
+            for id in chain.file_ids.iter().rev() {
+                let file = &self.files[*id];
+                if let Some(rule) = file.matching_rule(path, directory) {
+                    if !rule.ok {
+                        return false;
+                    }
+                    break;
                 }
-                let ok = if rule.filename {
-                    rule.pattern.matches_with(filename, rule.pattern_options)
-                } else {
-                    rule.pattern.matches_path_with(path, rule.pattern_options)
-                };
-                if ok {
-                    return rule.ok;
+            }
+        }
+        if self.dedicated_ignore_enabled {
+            for id in chain.dedicated_file_ids.iter().rev() {
+                let file = &self.files[*id];
+                if let Some(rule) = file.matching_rule(path, directory) {
+                    if !rule.ok {
+                        return false;
+                    }
+                    break;
                 }
             }
         }
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Write};
+
+    /// a bare, wildcard-less, filename-anchored pattern (e.g. `target`,
+    /// as found in this repo's own .gitignore) must ignore the file at
+    /// any depth, not just when it's exactly at the gitignore's location
+    #[test]
+    fn filename_only_pattern_matches_at_any_depth() {
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        writeln!(tmp, "target").unwrap();
+        let gif = GitIgnoreFile::new(tmp.path(), Path::new("/repo")).unwrap();
+
+        assert_eq!(
+            gif.matching_rule(Path::new("target"), true).map(|r| r.ok),
+            Some(false),
+        );
+        assert_eq!(
+            gif.matching_rule(Path::new("foo/target"), true).map(|r| r.ok),
+            Some(false),
+        );
+        assert_eq!(
+            gif.matching_rule(Path::new("foo/bar/target"), true).map(|r| r.ok),
+            Some(false),
+        );
+        assert!(gif.matching_rule(Path::new("foo/targetx"), true).is_none());
+    }
+
+    /// a wildcard filename pattern (e.g. `*.log`) must still match within
+    /// a single path component, not just the bare filename
+    #[test]
+    fn filename_wildcard_pattern_matches_at_any_depth() {
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        writeln!(tmp, "*.log").unwrap();
+        let gif = GitIgnoreFile::new(tmp.path(), Path::new("/repo")).unwrap();
+
+        assert_eq!(
+            gif.matching_rule(Path::new("foo/bar.log"), false).map(|r| r.ok),
+            Some(false),
+        );
+        assert!(gif.matching_rule(Path::new("foo/bar.txt"), false).is_none());
+    }
+
+    /// the dedicated `.ignore`/`.brootignore` chain must not be reset when
+    /// descending into a nested git repository, unlike the gitignore chain
+    #[test]
+    fn deeper_chain_keeps_dedicated_ignore_files_across_repo_boundary() {
+        let mut ignorer = GitIgnorer::default();
+        let mut parent_chain = GitIgnoreChain::default();
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        writeln!(tmp, "secret.txt").unwrap();
+        let gif = GitIgnoreFile::new(tmp.path(), Path::new("/repo")).unwrap();
+        parent_chain.push_dedicated(ignorer.files.alloc(gif));
+
+        let nested_repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(nested_repo.path().join(".git")).unwrap();
+
+        let chain = ignorer.deeper_chain(&parent_chain, nested_repo.path());
+        assert_eq!(chain.dedicated_file_ids, parent_chain.dedicated_file_ids);
+    }
+
+    /// override globs take priority over everything else: a plain pattern
+    /// force-hides a path even outside of any repo, and a `!`-prefixed one
+    /// force-shows it
+    #[test]
+    fn overrides_take_priority() {
+        let mut ignorer = GitIgnorer::default();
+        ignorer.overrides = Override::new(&[
+            "node_modules".to_string(),
+            "!.env".to_string(),
+        ]);
+        let chain = GitIgnoreChain::default(); // not in a repo, no ignore files at all
+
+        assert!(!ignorer.accepts(&chain, Path::new("node_modules"), "node_modules", true));
+        assert!(!ignorer.accepts(&chain, Path::new("a/node_modules"), "node_modules", true));
+        assert!(ignorer.accepts(&chain, Path::new(".env"), ".env", false));
+        assert!(ignorer.accepts(&chain, Path::new("readme.md"), "readme.md", false));
+    }
+
+    /// for a linked worktree, `$GIT_DIR` (`.git/worktrees/<name>`) has no
+    /// `info/` directory of its own: `info/exclude` must be resolved via
+    /// the common dir found in its `commondir` file
+    #[test]
+    fn info_exclude_resolves_linked_worktree_commondir() {
+        let main_repo = tempfile::tempdir().unwrap();
+        let git_dir = main_repo.path().join(".git");
+        std::fs::create_dir_all(git_dir.join("info")).unwrap();
+        std::fs::write(git_dir.join("info/exclude"), "ignored_in_worktree\n").unwrap();
+
+        let worktrees_dir = git_dir.join("worktrees/wt1");
+        std::fs::create_dir_all(&worktrees_dir).unwrap();
+        std::fs::write(worktrees_dir.join("commondir"), "../..\n").unwrap();
+
+        let worktree_repo = tempfile::tempdir().unwrap();
+        std::fs::write(
+            worktree_repo.path().join(".git"),
+            format!("gitdir: {}\n", worktrees_dir.display()),
+        )
+        .unwrap();
+
+        let gif = GitIgnoreFile::info_exclude(worktree_repo.path());
+        assert!(gif.is_some());
+        let gif = gif.unwrap();
+        assert_eq!(
+            gif.matching_rule(Path::new("ignored_in_worktree"), false).map(|r| r.ok),
+            Some(false),
+        );
+    }
+}
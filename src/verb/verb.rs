@@ -3,6 +3,7 @@ use {
     crate::{
         app::{Selection, SelInfo, SelectionType},
         errors::ConfError,
+        file_types::FileTypes,
         keys,
         path::{self, PathAnchor},
     },
@@ -48,6 +49,10 @@ pub struct Verb {
     /// the type of selection this verb applies to
     pub selection_condition: SelectionType,
 
+    /// if set, the verb only applies to files of this named type (as
+    /// defined in the `FileTypes` registry, e.g. "rust" or "web")
+    pub file_type: Option<String>,
+
     /// whether the verb needs a selection
     pub needs_selection: bool,
 
@@ -97,6 +102,7 @@ impl Verb {
             execution,
             description,
             selection_condition: SelectionType::Any,
+            file_type: None,
             needs_selection,
             needs_another_panel,
             auto_exec: true,
@@ -151,6 +157,19 @@ impl Verb {
         self.selection_condition = stype;
         self
     }
+    /// restrict this verb to files of the named type (see `FileTypes`)
+    pub fn with_file_type(mut self, type_name: &str) -> Self {
+        self.file_type = Some(type_name.to_string());
+        self
+    }
+    /// whether the verb applies to the given selection, as far as its
+    /// `file_type` restriction (if any) is concerned
+    pub fn matches_file_type(&self, file_types: &FileTypes, sel: &Selection<'_>) -> bool {
+        match &self.file_type {
+            Some(type_name) => file_types.is_of_type(sel.path, type_name),
+            None => true,
+        }
+    }
     pub fn needing_another_panel(mut self) -> Self {
         self.needs_another_panel = true;
         self
@@ -165,13 +184,14 @@ impl Verb {
     /// and return the error to display if arguments don't match.
     pub fn check_args(
         &self,
+        file_types: &FileTypes,
         sel_info: &SelInfo<'_>,
         invocation: &VerbInvocation,
         other_path: &Option<PathBuf>,
     ) -> Option<String> {
         match sel_info {
-            SelInfo::None => self.check_sel_args(None, invocation, other_path),
-            SelInfo::One(sel) => self.check_sel_args(Some(*sel), invocation, other_path),
+            SelInfo::None => self.check_sel_args(file_types, None, invocation, other_path),
+            SelInfo::One(sel) => self.check_sel_args(file_types, Some(*sel), invocation, other_path),
             SelInfo::More(stage) => {
                 stage.paths().iter()
                     .filter_map(|path| {
@@ -181,7 +201,7 @@ impl Verb {
                             stype: SelectionType::from(path),
                             is_exe: false,
                         };
-                        self.check_sel_args(Some(sel), invocation, other_path)
+                        self.check_sel_args(file_types, Some(sel), invocation, other_path)
                     })
                     .next()
             }
@@ -190,15 +210,26 @@ impl Verb {
 
     fn check_sel_args(
         &self,
+        file_types: &FileTypes,
         sel: Option<Selection<'_>>,
         invocation: &VerbInvocation,
         other_path: &Option<PathBuf>,
     ) -> Option<String> {
         if self.needs_selection && sel.is_none() {
-            Some("This verb needs a selection".to_string())
-        } else if self.needs_another_panel && other_path.is_none() {
-            Some("This verb needs exactly two panels".to_string())
-        } else if let Some(ref parser) = self.invocation_parser {
+            return Some("This verb needs a selection".to_string());
+        }
+        if self.needs_another_panel && other_path.is_none() {
+            return Some("This verb needs exactly two panels".to_string());
+        }
+        if let Some(sel) = &sel {
+            if !self.matches_file_type(file_types, sel) {
+                return Some(format!(
+                    "This verb only applies to {} files",
+                    self.file_type.as_deref().unwrap_or("?"),
+                ));
+            }
+        }
+        if let Some(ref parser) = self.invocation_parser {
             parser.check_args(invocation, other_path)
         } else if invocation.args.is_some() {
             Some("This verb doesn't take arguments".to_string())